@@ -1,3 +1,14 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::RwLock;
+use sha2::{Digest, Sha256};
+use warp::http::header::{HeaderValue, CONTENT_ENCODING, CONTENT_LENGTH};
+use warp::http::StatusCode;
+use warp::ws::{Message, WebSocket};
 use warp::Filter;
 use serde::{Deserialize, Serialize};
 
@@ -7,8 +18,312 @@ struct ApiResponse {
     status: String,
 }
 
-#[tokio::main]
-async fn main() {
+#[derive(Clone, Serialize, Deserialize)]
+struct Item {
+    id: u64,
+    name: String,
+    description: String,
+}
+
+type Db = Arc<RwLock<HashMap<u64, Item>>>;
+
+/// Compression algorithm applied to responses, selectable via `RUST_PROJECT_COMPRESSION`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    Gzip,
+    Brotli,
+    Deflate,
+}
+
+impl Compression {
+    fn from_env() -> Self {
+        match std::env::var("RUST_PROJECT_COMPRESSION").as_deref() {
+            Ok("brotli") => Compression::Brotli,
+            Ok("deflate") => Compression::Deflate,
+            _ => Compression::Gzip,
+        }
+    }
+
+    fn content_coding(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Brotli => "br",
+            Compression::Deflate => "deflate",
+        }
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            Compression::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(&mut out, flate2::Compression::default());
+                encoder.write_all(bytes).expect("gzip encoding is infallible for an in-memory buffer");
+                encoder.finish().expect("gzip encoding is infallible for an in-memory buffer");
+            }
+            Compression::Deflate => {
+                let mut encoder = flate2::write::DeflateEncoder::new(&mut out, flate2::Compression::default());
+                encoder.write_all(bytes).expect("deflate encoding is infallible for an in-memory buffer");
+                encoder.finish().expect("deflate encoding is infallible for an in-memory buffer");
+            }
+            Compression::Brotli => {
+                let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 5, 22);
+                writer.write_all(bytes).expect("brotli encoding is infallible for an in-memory buffer");
+                writer.flush().expect("brotli encoding is infallible for an in-memory buffer");
+            }
+        }
+        out
+    }
+}
+
+// Only compress when the client actually advertised support for this algorithm via
+// `Accept-Encoding`; warp's own `warp::compression::*` wraps always encode regardless
+// of the request, which breaks clients that send `Accept-Encoding: identity`.
+fn client_accepts(accept_encoding: Option<&str>, coding: &str) -> bool {
+    accept_encoding
+        .map(|value| {
+            value
+                .split(',')
+                // strip q-values and other parameters, e.g. "gzip;q=0.8" -> "gzip"
+                .filter_map(|candidate| candidate.split(';').next())
+                .any(|candidate| candidate.trim().eq_ignore_ascii_case(coding))
+        })
+        .unwrap_or(false)
+}
+
+async fn maybe_compress(
+    accept_encoding: Option<String>,
+    algorithm: Compression,
+    reply: impl warp::Reply,
+) -> Result<warp::reply::Response, std::convert::Infallible> {
+    let response = reply.into_response();
+
+    // Never touch the 101 Switching Protocols reply from the WebSocket routes: its
+    // body is a placeholder and the real traffic takes over the connection after
+    // hyper hands off the upgrade, so rewriting it here would corrupt the handshake.
+    if response.status() == StatusCode::SWITCHING_PROTOCOLS {
+        return Ok(response);
+    }
+
+    let coding = algorithm.content_coding();
+    let (mut parts, body) = response.into_parts();
+    parts.headers.append(warp::http::header::VARY, HeaderValue::from_static("accept-encoding"));
+
+    if !client_accepts(accept_encoding.as_deref(), coding) {
+        return Ok(warp::http::Response::from_parts(parts, body));
+    }
+
+    let bytes = warp::hyper::body::to_bytes(body).await.unwrap_or_default();
+    let compressed = algorithm.encode(&bytes);
+
+    parts.headers.insert(CONTENT_ENCODING, HeaderValue::from_static(coding));
+    parts.headers.remove(CONTENT_LENGTH);
+    Ok(warp::http::Response::from_parts(
+        parts,
+        warp::hyper::Body::from(compressed),
+    ))
+}
+
+fn with_db(db: Db) -> impl Filter<Extract = (Db,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || db.clone())
+}
+
+type BlobStore = Arc<RwLock<HashMap<String, Bytes>>>;
+
+fn with_blobs(blobs: BlobStore) -> impl Filter<Extract = (BlobStore,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || blobs.clone())
+}
+
+fn sha256_digest(bytes: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(bytes))
+}
+
+#[derive(Debug)]
+struct DigestMismatch;
+impl warp::reject::Reject for DigestMismatch {}
+
+#[derive(Deserialize)]
+struct DigestQuery {
+    digest: Option<String>,
+}
+
+fn expected_digest() -> impl Filter<Extract = (Option<String>,), Error = warp::Rejection> + Clone {
+    warp::query::<DigestQuery>()
+        .and(warp::header::optional::<String>("x-expected-digest"))
+        .map(|query: DigestQuery, header: Option<String>| query.digest.or(header))
+}
+
+// PUT /blobs
+async fn put_blob(
+    expected: Option<String>,
+    body: Bytes,
+    blobs: BlobStore,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    let digest = sha256_digest(&body);
+
+    if let Some(expected) = expected {
+        if expected != digest {
+            return Err(warp::reject::custom(DigestMismatch));
+        }
+    }
+
+    blobs.write().insert(digest.clone(), body);
+    Ok(Box::new(warp::reply::json(&serde_json::json!({ "digest": digest }))))
+}
+
+// GET /blobs/:digest
+async fn get_blob(digest: String, blobs: BlobStore) -> Result<Box<dyn warp::Reply>, std::convert::Infallible> {
+    match blobs.read().get(&digest).cloned() {
+        Some(bytes) => Ok(Box::new(bytes.to_vec())),
+        None => Ok(Box::new(StatusCode::NOT_FOUND)),
+    }
+}
+
+// HEAD /blobs/:digest
+async fn head_blob(digest: String, blobs: BlobStore) -> Result<Box<dyn warp::Reply>, std::convert::Infallible> {
+    if blobs.read().contains_key(&digest) {
+        Ok(Box::new(StatusCode::OK))
+    } else {
+        Ok(Box::new(StatusCode::NOT_FOUND))
+    }
+}
+
+// GET /api/items
+async fn list_items(db: Db) -> Result<impl warp::Reply, std::convert::Infallible> {
+    let items: Vec<Item> = db.read().values().cloned().collect();
+    Ok(warp::reply::json(&items))
+}
+
+// POST /api/items
+async fn create_item(mut item: Item, db: Db) -> Result<impl warp::Reply, std::convert::Infallible> {
+    let mut store = db.write();
+    let id = store.keys().max().map_or(1, |max| max + 1);
+    item.id = id;
+    store.insert(id, item.clone());
+    drop(store);
+    Ok(warp::reply::with_status(
+        warp::reply::json(&item),
+        StatusCode::CREATED,
+    ))
+}
+
+// PUT /api/items/:id
+async fn update_item(id: u64, mut item: Item, db: Db) -> Result<Box<dyn warp::Reply>, std::convert::Infallible> {
+    let mut store = db.write();
+    if !store.contains_key(&id) {
+        return Ok(Box::new(StatusCode::NOT_FOUND));
+    }
+    item.id = id;
+    store.insert(id, item.clone());
+    Ok(Box::new(warp::reply::json(&item)))
+}
+
+// DELETE /api/items/:id
+async fn delete_item(id: u64, db: Db) -> Result<Box<dyn warp::Reply>, std::convert::Infallible> {
+    let mut store = db.write();
+    if store.remove(&id).is_none() {
+        return Ok(Box::new(StatusCode::NOT_FOUND));
+    }
+    Ok(Box::new(StatusCode::NO_CONTENT))
+}
+
+// GET /hello/:name
+async fn greet(name: String, user_agent: String) -> Result<impl warp::Reply, std::convert::Infallible> {
+    let response = ApiResponse {
+        message: format!("Hello, {} from {}!", name, user_agent),
+        status: "success".to_string(),
+    };
+    Ok(warp::reply::json(&response))
+}
+
+async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, std::convert::Infallible> {
+    if let Some(e) = err.find::<warp::reject::MissingHeader>() {
+        let response = ApiResponse {
+            message: format!("Missing required header: {}", e.name()),
+            status: "error".to_string(),
+        };
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&response),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if err.find::<DigestMismatch>().is_some() {
+        let response = ApiResponse {
+            message: "Computed digest does not match the claimed digest".to_string(),
+            status: "error".to_string(),
+        };
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&response),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let (status, message) = if err.is_not_found() {
+        (StatusCode::NOT_FOUND, "Not found".to_string())
+    } else if let Some(e) = err.find::<warp::filters::body::BodyDeserializeError>() {
+        (StatusCode::BAD_REQUEST, e.to_string())
+    } else if let Some(e) = err.find::<warp::reject::InvalidQuery>() {
+        (StatusCode::BAD_REQUEST, e.to_string())
+    } else if let Some(e) = err.find::<warp::reject::UnsupportedMediaType>() {
+        // Checked ahead of `MethodNotAllowed`: a bad Content-Type on a path that also
+        // has a sibling route for another method rejects with both causes, and the
+        // real problem here is the media type, not the method.
+        (StatusCode::UNSUPPORTED_MEDIA_TYPE, e.to_string())
+    } else if let Some(e) = err.find::<warp::reject::MethodNotAllowed>() {
+        (StatusCode::METHOD_NOT_ALLOWED, e.to_string())
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
+    };
+
+    let response = ApiResponse {
+        message,
+        status: "error".to_string(),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&response), status))
+}
+
+// GET /ws/echo
+async fn handle_echo(ws: WebSocket) {
+    let (mut tx, mut rx) = ws.split();
+    while let Some(result) = rx.next().await {
+        let msg = match result {
+            Ok(msg) => msg,
+            Err(_) => break,
+        };
+        if msg.is_close() {
+            break;
+        }
+        if (msg.is_text() || msg.is_binary()) && tx.send(msg).await.is_err() {
+            break;
+        }
+    }
+}
+
+// GET /ws/reverse
+async fn handle_reverse(ws: WebSocket) {
+    let (mut tx, mut rx) = ws.split();
+    while let Some(result) = rx.next().await {
+        let msg = match result {
+            Ok(msg) => msg,
+            Err(_) => break,
+        };
+        if msg.is_close() {
+            break;
+        }
+        if let Ok(text) = msg.to_str() {
+            let reversed: String = text.chars().rev().collect();
+            if tx.send(Message::text(reversed)).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+pub fn api(compression: Compression) -> warp::filters::BoxedFilter<(impl warp::Reply,)> {
+    let db: Db = Arc::new(RwLock::new(HashMap::new()));
+    let blobs: BlobStore = Arc::new(RwLock::new(HashMap::new()));
+
     // GET /
     let hello = warp::path::end()
         .map(|| {
@@ -42,18 +357,340 @@ async fn main() {
             warp::reply::json(&response)
         });
 
+    // GET /api/items
+    let items_list = warp::path!("api" / "items")
+        .and(warp::get())
+        .and(with_db(db.clone()))
+        .and_then(list_items);
+
+    // POST /api/items
+    let items_create = warp::path!("api" / "items")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_db(db.clone()))
+        .and_then(create_item);
+
+    // PUT /api/items/:id
+    let items_update = warp::path!("api" / "items" / u64)
+        .and(warp::put())
+        .and(warp::body::json())
+        .and(with_db(db.clone()))
+        .and_then(update_item);
+
+    // DELETE /api/items/:id
+    let items_delete = warp::path!("api" / "items" / u64)
+        .and(warp::delete())
+        .and(with_db(db.clone()))
+        .and_then(delete_item);
+
+    let items = items_list
+        .or(items_create)
+        .or(items_update)
+        .or(items_delete);
+
+    // GET /hello/:name
+    let hello_name = warp::path!("hello" / String)
+        .and(warp::get())
+        .and(warp::header::<String>("user-agent"))
+        .and_then(greet);
+
+    // PUT /blobs
+    let blobs_put = warp::path!("blobs")
+        .and(warp::put())
+        .and(expected_digest())
+        .and(warp::body::bytes())
+        .and(with_blobs(blobs.clone()))
+        .and_then(put_blob);
+
+    // GET /blobs/:digest
+    let blobs_get = warp::path!("blobs" / String)
+        .and(warp::get())
+        .and(with_blobs(blobs.clone()))
+        .and_then(get_blob);
+
+    // HEAD /blobs/:digest
+    let blobs_head = warp::path!("blobs" / String)
+        .and(warp::head())
+        .and(with_blobs(blobs.clone()))
+        .and_then(head_blob);
+
+    let blobs_routes = blobs_put.or(blobs_get).or(blobs_head);
+
+    // GET /ws/echo
+    let ws_echo = warp::path!("ws" / "echo")
+        .and(warp::ws())
+        .map(|ws: warp::ws::Ws| ws.on_upgrade(handle_echo));
+
+    // GET /ws/reverse
+    let ws_reverse = warp::path!("ws" / "reverse")
+        .and(warp::ws())
+        .map(|ws: warp::ws::Ws| ws.on_upgrade(handle_reverse));
+
     let routes = hello
         .or(health)
         .or(info)
-        .with(warp::cors().allow_any_origin());
+        .or(items)
+        .or(hello_name)
+        .or(blobs_routes)
+        .or(ws_echo)
+        .or(ws_reverse)
+        .recover(handle_rejection)
+        .with(warp::cors().allow_any_origin())
+        .with(warp::log("rust-project"));
+
+    // Only compress when the client's Accept-Encoding header actually advertises
+    // support for the configured algorithm; see `maybe_compress`.
+    routes
+        .and(warp::header::optional::<String>("accept-encoding"))
+        .and(warp::any().map(move || compression))
+        .and_then(|reply, accept_encoding, algorithm| maybe_compress(accept_encoding, algorithm, reply))
+        .boxed()
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
 
     println!("🚀 Server starting on http://0.0.0.0:8001");
     println!("📍 Endpoints:");
     println!("   GET /        - Welcome message");
     println!("   GET /health  - Health check");
     println!("   GET /api/info - Service information");
+    println!("   GET /api/items - List items");
+    println!("   POST /api/items - Create item");
+    println!("   PUT /api/items/:id - Replace item");
+    println!("   DELETE /api/items/:id - Delete item");
+    println!("   GET /hello/:name - Personalized greeting");
+    println!("   PUT /blobs - Upload a content-addressed blob");
+    println!("   GET /blobs/:digest - Fetch a blob");
+    println!("   HEAD /blobs/:digest - Check if a blob exists");
+    println!("   GET /ws/echo - WebSocket echo");
+    println!("   GET /ws/reverse - WebSocket reverse");
 
-    warp::serve(routes)
+    warp::serve(api(Compression::from_env()))
         .bind(([0, 0, 0, 0], 8001))
         .await;
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn gunzip(bytes: &[u8]) -> Vec<u8> {
+        let mut decoder = flate2::read::GzDecoder::new(bytes);
+        let mut out = Vec::new();
+        decoder.read_to_end(&mut out).unwrap();
+        out
+    }
+
+    #[tokio::test]
+    async fn health_returns_ok() {
+        let resp = warp::test::request()
+            .method("GET")
+            .path("/health")
+            .reply(&api(Compression::default()))
+            .await;
+
+        assert_eq!(resp.status(), 200);
+        let body: ApiResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(body.status, "ok");
+    }
+
+    #[tokio::test]
+    async fn info_contains_version() {
+        let resp = warp::test::request()
+            .method("GET")
+            .path("/api/info")
+            .reply(&api(Compression::default()))
+            .await;
+
+        assert_eq!(resp.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(body["version"], "0.1.0");
+    }
+
+    #[tokio::test]
+    async fn health_is_only_compressed_when_accepted() {
+        let api = api(Compression::default());
+
+        let plain = warp::test::request()
+            .method("GET")
+            .path("/health")
+            .header("accept-encoding", "identity")
+            .reply(&api)
+            .await;
+        assert!(plain.headers().get("content-encoding").is_none());
+        let body: ApiResponse = serde_json::from_slice(plain.body()).unwrap();
+        assert_eq!(body.status, "ok");
+
+        let compressed = warp::test::request()
+            .method("GET")
+            .path("/health")
+            .header("accept-encoding", "gzip")
+            .reply(&api)
+            .await;
+        assert_eq!(compressed.headers().get("content-encoding").unwrap(), "gzip");
+        assert_eq!(compressed.headers().get("vary").unwrap(), "accept-encoding");
+        let body: ApiResponse = serde_json::from_slice(&gunzip(compressed.body())).unwrap();
+        assert_eq!(body.status, "ok");
+
+        // Accept-Encoding entries may carry q-values, e.g. "gzip;q=0.8" - the coding
+        // name itself must still match.
+        let weighted = warp::test::request()
+            .method("GET")
+            .path("/health")
+            .header("accept-encoding", "br;q=0.9, gzip;q=0.8")
+            .reply(&api)
+            .await;
+        assert_eq!(weighted.headers().get("content-encoding").unwrap(), "gzip");
+    }
+
+    #[tokio::test]
+    async fn unknown_path_is_404() {
+        let resp = warp::test::request()
+            .method("GET")
+            .path("/does/not/exist")
+            .reply(&api(Compression::default()))
+            .await;
+
+        assert_eq!(resp.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn items_crud_roundtrip() {
+        let api = api(Compression::default());
+
+        let create = warp::test::request()
+            .method("POST")
+            .path("/api/items")
+            .header("content-type", "application/json")
+            .json(&serde_json::json!({"id": 0, "name": "Widget", "description": "A widget"}))
+            .reply(&api)
+            .await;
+        assert_eq!(create.status(), 201);
+        let created: Item = serde_json::from_slice(create.body()).unwrap();
+
+        let list = warp::test::request()
+            .method("GET")
+            .path("/api/items")
+            .reply(&api)
+            .await;
+        assert_eq!(list.status(), 200);
+        let items: Vec<Item> = serde_json::from_slice(list.body()).unwrap();
+        assert!(items.iter().any(|item| item.id == created.id));
+
+        let update = warp::test::request()
+            .method("PUT")
+            .path(&format!("/api/items/{}", created.id))
+            .header("content-type", "application/json")
+            .json(&serde_json::json!({"id": 0, "name": "Widget v2", "description": "Updated"}))
+            .reply(&api)
+            .await;
+        assert_eq!(update.status(), 200);
+
+        let delete = warp::test::request()
+            .method("DELETE")
+            .path(&format!("/api/items/{}", created.id))
+            .reply(&api)
+            .await;
+        assert_eq!(delete.status(), 204);
+
+        let delete_again = warp::test::request()
+            .method("DELETE")
+            .path(&format!("/api/items/{}", created.id))
+            .reply(&api)
+            .await;
+        assert_eq!(delete_again.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn hello_name_returns_personalized_message() {
+        let resp = warp::test::request()
+            .method("GET")
+            .path("/hello/world")
+            .header("user-agent", "test-agent")
+            .reply(&api(Compression::default()))
+            .await;
+
+        assert_eq!(resp.status(), 200);
+        let body: ApiResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(body.message, "Hello, world from test-agent!");
+    }
+
+    #[tokio::test]
+    async fn hello_name_missing_user_agent_is_400() {
+        let resp = warp::test::request()
+            .method("GET")
+            .path("/hello/world")
+            .reply(&api(Compression::default()))
+            .await;
+
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn blobs_put_get_head_roundtrip() {
+        let api = api(Compression::default());
+
+        let put = warp::test::request()
+            .method("PUT")
+            .path("/blobs")
+            .body("hello world")
+            .reply(&api)
+            .await;
+        assert_eq!(put.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(put.body()).unwrap();
+        let digest = body["digest"].as_str().unwrap().to_string();
+
+        let get = warp::test::request()
+            .method("GET")
+            .path(&format!("/blobs/{}", digest))
+            .reply(&api)
+            .await;
+        assert_eq!(get.status(), 200);
+        assert_eq!(&get.body()[..], b"hello world");
+
+        let head = warp::test::request()
+            .method("HEAD")
+            .path(&format!("/blobs/{}", digest))
+            .reply(&api)
+            .await;
+        assert_eq!(head.status(), 200);
+
+        let missing = warp::test::request()
+            .method("GET")
+            .path("/blobs/sha256:does-not-exist")
+            .reply(&api)
+            .await;
+        assert_eq!(missing.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn blobs_put_rejects_mismatched_digest() {
+        let resp = warp::test::request()
+            .method("PUT")
+            .path("/blobs?digest=sha256:not-the-real-digest")
+            .body("hello world")
+            .reply(&api(Compression::default()))
+            .await;
+
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn wrong_content_type_is_not_reported_as_method_not_allowed() {
+        // /api/items/:id has both a PUT and a DELETE route, so a bad Content-Type
+        // on the PUT also combines with a `MethodNotAllowed` cause from the DELETE
+        // sibling; `handle_rejection` must still surface the media type problem.
+        let resp = warp::test::request()
+            .method("PUT")
+            .path("/api/items/1")
+            .header("content-type", "text/plain")
+            .body("not json")
+            .reply(&api(Compression::default()))
+            .await;
+
+        assert_eq!(resp.status(), 415);
+    }
+}